@@ -23,6 +23,8 @@
 #![deny(missing_docs)]
 #![deny(clippy::all)]
 
+use std::iter::{once, Chain, Once};
+
 pub mod prelude;
 
 /// The difference between two iterator elements.
@@ -43,18 +45,589 @@ pub enum Diff<T> {
     Add(T),
 }
 
+/// A single step in a longest-common-subsequence edit script, expressed in
+/// terms of which side it advances.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LcsStep {
+    /// Advance both sides: the elements match.
+    Keep,
+    /// Advance the left-hand side only: the element is not in the rhs.
+    Remove,
+    /// Advance the right-hand side only: the element is not in the lhs.
+    Add,
+}
+
+/// Compute the edit script aligning `lhs[0..n]` with `rhs[0..m]` using the
+/// classic longest-common-subsequence dynamic program.
+///
+/// `dp[i][j]` holds the LCS length of `lhs[i..]` and `rhs[j..]`; it is filled
+/// bottom-up and then walked from `(0, 0)` to reconstruct a minimal sequence
+/// of [`LcsStep`]s. The `eq` closure reports whether `lhs[i]` and `rhs[j]` are
+/// equal, keeping this routine free of any element type.
+fn lcs_script(
+    n: usize,
+    m: usize,
+    mut eq: impl FnMut(usize, usize) -> bool,
+) -> Vec<LcsStep> {
+    let mut dp = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if eq(i, j) {
+                1 + dp[i + 1][j + 1]
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut steps = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if eq(i, j) {
+            steps.push(LcsStep::Keep);
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            steps.push(LcsStep::Remove);
+            i += 1;
+        } else {
+            steps.push(LcsStep::Add);
+            j += 1;
+        }
+    }
+    while i < n {
+        steps.push(LcsStep::Remove);
+        i += 1;
+    }
+    while j < m {
+        steps.push(LcsStep::Add);
+        j += 1;
+    }
+    steps
+}
+
+/// A run of [`LcsStep`]s of the same kind, carrying the index ranges it
+/// spans on each side.
+#[derive(Clone, Copy)]
+enum Block {
+    /// A run of matching elements, starting at `old`/`new` on each side.
+    Equal { old: usize, new: usize, len: usize },
+    /// A run of left-hand side elements absent from the right-hand side.
+    Delete { old: usize, len: usize },
+    /// A run of right-hand side elements absent from the left-hand side.
+    Insert { new: usize, len: usize },
+    /// An aligned run that differs on both sides.
+    Replace {
+        old: usize,
+        old_len: usize,
+        new: usize,
+        new_len: usize,
+    },
+}
+
+/// Group an LCS edit script into [`Block`]s, merging an adjacent
+/// delete/insert pair into a single [`Block::Replace`].
+fn lcs_blocks(
+    n: usize,
+    m: usize,
+    eq: impl FnMut(usize, usize) -> bool,
+) -> Vec<Block> {
+    let steps = lcs_script(n, m, eq);
+
+    let mut raw = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut k = 0;
+    while k < steps.len() {
+        let start = steps[k];
+        let mut len = 0;
+        while k < steps.len() && steps[k] == start {
+            len += 1;
+            k += 1;
+        }
+        match start {
+            LcsStep::Keep => {
+                raw.push(Block::Equal { old: i, new: j, len });
+                i += len;
+                j += len;
+            }
+            LcsStep::Remove => {
+                raw.push(Block::Delete { old: i, len });
+                i += len;
+            }
+            LcsStep::Add => {
+                raw.push(Block::Insert { new: j, len });
+                j += len;
+            }
+        }
+    }
+
+    let mut blocks = Vec::with_capacity(raw.len());
+    let mut idx = 0;
+    while idx < raw.len() {
+        match (raw[idx], raw.get(idx + 1).copied()) {
+            (
+                Block::Delete { old, len },
+                Some(Block::Insert { new, len: new_len }),
+            ) => {
+                blocks.push(Block::Replace {
+                    old,
+                    old_len: len,
+                    new,
+                    new_len,
+                });
+                idx += 2;
+            }
+            (
+                Block::Insert { new, len: new_len },
+                Some(Block::Delete { old, len }),
+            ) => {
+                blocks.push(Block::Replace {
+                    old,
+                    old_len: len,
+                    new,
+                    new_len,
+                });
+                idx += 2;
+            }
+            (block, _) => {
+                blocks.push(block);
+                idx += 1;
+            }
+        }
+    }
+    blocks
+}
+
+/// A span-based difference between two slices, grouping runs of the same
+/// kind so a block-oriented consumer can render or apply edits in one go.
+#[derive(Debug, Hash, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+pub enum DiffOp<'a, T> {
+    /// A run present unchanged in both slices.
+    Equal(&'a [T]),
+    /// A run present in the left-hand side only.
+    Remove(&'a [T]),
+    /// A run present in the right-hand side only.
+    Insert(&'a [T]),
+    /// An aligned run that differs, carrying the old and new spans together.
+    Replace(&'a [T], &'a [T]),
+}
+
+/// Diff two slices into coalesced [`DiffOp`]s over borrowed spans.
+///
+/// The elements are aligned by their longest common subsequence, then runs
+/// of the same kind are grouped and an adjacent remove/insert pair is merged
+/// into a single [`DiffOp::Replace`]. Operating on borrowed slices keeps the
+/// result allocation-light beyond the returned [`Vec`] of spans.
+///
+/// ```
+/// use iter_diff::prelude::*;
+///
+/// let a = [0, 1, 2];
+/// let b = [0, 9, 2];
+///
+/// let ops = slice_diff(&a, &b);
+/// assert_eq!(ops.len(), 3);
+///
+/// assert_eq!(ops[0], DiffOp::Equal(&[0]));
+/// assert_eq!(ops[1], DiffOp::Replace(&[1], &[9]));
+/// assert_eq!(ops[2], DiffOp::Equal(&[2]));
+/// ```
+pub fn slice_diff<'a, T>(lhs: &'a [T], rhs: &'a [T]) -> Vec<DiffOp<'a, T>>
+where
+    T: PartialEq,
+{
+    lcs_blocks(lhs.len(), rhs.len(), |i, j| lhs[i] == rhs[j])
+        .into_iter()
+        .map(|block| match block {
+            Block::Equal { old, len, .. } => {
+                DiffOp::Equal(&lhs[old..old + len])
+            }
+            Block::Delete { old, len } => DiffOp::Remove(&lhs[old..old + len]),
+            Block::Insert { new, len } => DiffOp::Insert(&rhs[new..new + len]),
+            Block::Replace {
+                old,
+                old_len,
+                new,
+                new_len,
+            } => DiffOp::Replace(
+                &lhs[old..old + old_len],
+                &rhs[new..new + new_len],
+            ),
+        })
+        .collect()
+}
+
+/// Group a positional comparison into [`Block`]s: aligned runs become
+/// [`Block::Equal`] or [`Block::Replace`], and the unmatched tail of the
+/// longer side becomes a single [`Block::Delete`] or [`Block::Insert`].
+fn positional_blocks(
+    n: usize,
+    m: usize,
+    mut eq: impl FnMut(usize, usize) -> bool,
+) -> Vec<Block> {
+    let min = n.min(m);
+    let mut blocks = Vec::new();
+    let mut k = 0;
+    while k < min {
+        let start = k;
+        let equal = eq(k, k);
+        while k < min && eq(k, k) == equal {
+            k += 1;
+        }
+        let len = k - start;
+        blocks.push(if equal {
+            Block::Equal { old: start, new: start, len }
+        } else {
+            Block::Replace {
+                old: start,
+                old_len: len,
+                new: start,
+                new_len: len,
+            }
+        });
+    }
+    if n > min {
+        blocks.push(Block::Delete { old: min, len: n - min });
+    } else if m > min {
+        blocks.push(Block::Insert { new: min, len: m - min });
+    }
+    blocks
+}
+
+/// Which alignment a [`drive_diff`] run uses to group events.
+#[derive(Debug, Hash, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+pub enum DiffMode {
+    /// Compare elements positionally (`lhs[i]` against `rhs[i]`), like
+    /// [`iter_diff`](IterDiff::iter_diff).
+    Positional,
+    /// Align elements by their longest common subsequence, like
+    /// [`iter_diff_lcs`](IterDiff::iter_diff_lcs).
+    Lcs,
+}
+
+/// A sink for grouped diff events, driven by [`drive_diff`].
+///
+/// Implementing this lets a consumer process a diff — building a histogram,
+/// applying a patch, rendering blocks — without ever materializing a
+/// `Vec<Diff>`. Every method reports a run by its start index into each
+/// original sequence it covers (the lhs for `delete`, the rhs for `insert`,
+/// both for `equal` and `replace`) and its length, given per side for
+/// `replace`; the default
+/// implementations ignore the event so an implementor need only override the
+/// ones it cares about. Returning `Err` from any callback aborts the run and
+/// surfaces the error to the caller.
+pub trait DiffHook {
+    /// The error type a callback may return to abort the run.
+    type Error;
+
+    /// Called for a run of `len` elements equal on both sides, starting at
+    /// `old_index` in the lhs and `new_index` in the rhs.
+    fn equal(
+        &mut self,
+        old_index: usize,
+        new_index: usize,
+        len: usize,
+    ) -> Result<(), Self::Error> {
+        let _ = (old_index, new_index, len);
+        Ok(())
+    }
+
+    /// Called for a run of `len` lhs elements, starting at `old_index`, that
+    /// are absent from the rhs.
+    fn delete(
+        &mut self,
+        old_index: usize,
+        len: usize,
+    ) -> Result<(), Self::Error> {
+        let _ = (old_index, len);
+        Ok(())
+    }
+
+    /// Called for a run of `len` rhs elements, starting at `new_index`, that
+    /// are absent from the lhs.
+    fn insert(
+        &mut self,
+        new_index: usize,
+        len: usize,
+    ) -> Result<(), Self::Error> {
+        let _ = (new_index, len);
+        Ok(())
+    }
+
+    /// Called for an aligned run that differs: `old_len` lhs elements at
+    /// `old_index` are replaced by `new_len` rhs elements at `new_index`.
+    fn replace(
+        &mut self,
+        old_index: usize,
+        old_len: usize,
+        new_index: usize,
+        new_len: usize,
+    ) -> Result<(), Self::Error> {
+        let _ = (old_index, old_len, new_index, new_len);
+        Ok(())
+    }
+}
+
+/// Diff two slices and dispatch the grouped events into a [`DiffHook`].
+///
+/// The elements are aligned according to `mode`, coalesced into runs, and
+/// reported through the hook's callbacks. The run short-circuits and returns
+/// the error from the first callback that fails.
+///
+/// ```
+/// use iter_diff::prelude::*;
+///
+/// #[derive(Default)]
+/// struct Counts {
+///     equal: usize,
+///     inserted: usize,
+/// }
+///
+/// impl DiffHook for Counts {
+///     type Error = ();
+///
+///     fn equal(&mut self, _: usize, _: usize, len: usize) -> Result<(), ()> {
+///         self.equal += len;
+///         Ok(())
+///     }
+///
+///     fn insert(&mut self, _: usize, len: usize) -> Result<(), ()> {
+///         self.inserted += len;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut counts = Counts::default();
+/// drive_diff(DiffMode::Lcs, &[0, 1, 2], &[0, 1, 2, 3], &mut counts).unwrap();
+///
+/// assert_eq!(counts.equal, 3);
+/// assert_eq!(counts.inserted, 1);
+/// ```
+pub fn drive_diff<T, H>(
+    mode: DiffMode,
+    lhs: &[T],
+    rhs: &[T],
+    hook: &mut H,
+) -> Result<(), H::Error>
+where
+    T: PartialEq,
+    H: DiffHook,
+{
+    let eq = |i: usize, j: usize| lhs[i] == rhs[j];
+    let blocks = match mode {
+        DiffMode::Positional => positional_blocks(lhs.len(), rhs.len(), eq),
+        DiffMode::Lcs => lcs_blocks(lhs.len(), rhs.len(), eq),
+    };
+    for block in blocks {
+        match block {
+            Block::Equal { old, new, len } => hook.equal(old, new, len)?,
+            Block::Delete { old, len } => hook.delete(old, len)?,
+            Block::Insert { new, len } => hook.insert(new, len)?,
+            Block::Replace {
+                old,
+                old_len,
+                new,
+                new_len,
+            } => hook.replace(old, old_len, new, new_len)?,
+        }
+    }
+    Ok(())
+}
+
+/// A lazy iterator reconstructing the right-hand side from a base sequence
+/// and a diff, returned by [`apply`].
+///
+/// Each [`Diff`] drives one step: [`Keep`](Diff::Keep) advances the base and
+/// yields its element, [`Change`](Diff::Change) advances the base but yields
+/// the new element, [`Remove`](Diff::Remove) advances the base and yields
+/// nothing, and [`Add`](Diff::Add) yields the new element without advancing.
+/// A [`Keep`](Diff::Keep) or [`Remove`](Diff::Remove) past the end of the base
+/// is silently skipped; use [`apply_checked`] to reject such a diff instead.
+#[derive(Debug, Hash, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+pub struct Apply<Lhs, Diffs> {
+    lhs: Lhs,
+    diffs: Diffs,
+}
+
+impl<T, Lhs, Diffs> Iterator for Apply<Lhs, Diffs>
+where
+    Lhs: Iterator<Item = T>,
+    Diffs: Iterator<Item = Diff<T>>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.diffs.next()? {
+                Diff::Keep => {
+                    if let Some(t) = self.lhs.next() {
+                        return Some(t);
+                    }
+                }
+                Diff::Change(t) => {
+                    self.lhs.next();
+                    return Some(t);
+                }
+                Diff::Remove => {
+                    self.lhs.next();
+                }
+                Diff::Add(t) => return Some(t),
+            }
+        }
+    }
+}
+
+/// Why [`apply_checked`] could not replay a diff against its base.
+#[derive(Debug, Hash, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ApplyError {
+    /// A [`Keep`](Diff::Keep), [`Change`](Diff::Change) or
+    /// [`Remove`](Diff::Remove) referred to a base element, but the base was
+    /// already exhausted.
+    BaseExhausted,
+    /// The diff was fully consumed while the base still held elements.
+    BaseNotConsumed,
+}
+
+/// Replay a diff against a base sequence, yielding the reconstructed
+/// right-hand side lazily.
+///
+/// This is the inverse of [`iter_diff`](IterDiff::iter_diff) and
+/// [`iter_diff_lcs`](IterDiff::iter_diff_lcs): given the original left-hand
+/// side and the diff between it and some right-hand side, it replays the edits
+/// to yield that right-hand side. See [`apply_checked`] for a variant that
+/// validates the diff against the base length.
+///
+/// ```
+/// use iter_diff::prelude::*;
+///
+/// let a = [0, 1, 2, 3];
+/// let b = [9, 0, 1, 2, 3];
+///
+/// let diffs = a.iter_diff_lcs(b);
+/// let rebuilt: Vec<_> = apply(a, diffs).collect();
+/// assert_eq!(rebuilt, b);
+/// ```
+pub fn apply<T, Lhs, Diffs>(
+    lhs: Lhs,
+    diffs: Diffs,
+) -> Apply<Lhs::IntoIter, Diffs::IntoIter>
+where
+    Lhs: IntoIterator<Item = T>,
+    Diffs: IntoIterator<Item = Diff<T>>,
+{
+    Apply { lhs: lhs.into_iter(), diffs: diffs.into_iter() }
+}
+
+/// Replay a diff against a base sequence, erroring if the two disagree.
+///
+/// Like [`apply`], but eager and checked: a [`Keep`](Diff::Keep),
+/// [`Change`](Diff::Change) or [`Remove`](Diff::Remove) that runs past the end
+/// of the base fails with [`ApplyError::BaseExhausted`], and a base left with
+/// surplus elements once the diff is consumed fails with
+/// [`ApplyError::BaseNotConsumed`].
+///
+/// ```
+/// use iter_diff::prelude::*;
+///
+/// let a = [0, 1, 2, 3];
+/// let b = [0, 9, 2];
+///
+/// let diffs: Vec<_> = a.iter_diff(b).collect();
+/// assert_eq!(apply_checked(a, diffs), Ok(b.to_vec()));
+///
+/// assert_eq!(
+///     apply_checked([0, 1], [Diff::Keep, Diff::Keep, Diff::Keep]),
+///     Err(ApplyError::BaseExhausted),
+/// );
+/// ```
+pub fn apply_checked<T, Lhs, Diffs>(
+    lhs: Lhs,
+    diffs: Diffs,
+) -> Result<Vec<T>, ApplyError>
+where
+    Lhs: IntoIterator<Item = T>,
+    Diffs: IntoIterator<Item = Diff<T>>,
+{
+    let mut lhs = lhs.into_iter();
+    let mut out = Vec::new();
+    for diff in diffs {
+        match diff {
+            Diff::Keep => {
+                out.push(lhs.next().ok_or(ApplyError::BaseExhausted)?)
+            }
+            Diff::Change(t) => {
+                lhs.next().ok_or(ApplyError::BaseExhausted)?;
+                out.push(t);
+            }
+            Diff::Remove => {
+                lhs.next().ok_or(ApplyError::BaseExhausted)?;
+            }
+            Diff::Add(t) => out.push(t),
+        }
+    }
+    if lhs.next().is_some() {
+        return Err(ApplyError::BaseNotConsumed);
+    }
+    Ok(out)
+}
+
+/// An iterator handed back by [`first_mismatch`](IterDiff::first_mismatch)
+/// with its already-consumed element pushed back in front, so it is
+/// positioned at the point of divergence.
+pub type Rejoined<T, I> = Chain<Once<T>, I>;
+
+/// Where two iterators first diverge, returned by
+/// [`first_mismatch`](IterDiff::first_mismatch).
+///
+/// Each non-[`Equal`](Divergence::Equal) variant hands back the un-consumed
+/// tails repositioned at the divergence, so the caller can decide what to do
+/// without collecting a whole `Vec<Diff>`.
+#[derive(Debug, Clone)]
+pub enum Divergence<Lhs, Rhs> {
+    /// Both iterators yielded an unequal pair at this index. The tails are
+    /// repositioned so their next elements are the differing pair.
+    FirstMismatch(usize, Lhs, Rhs),
+    /// The left-hand side ran out first, after this many matching elements.
+    /// The right-hand side tail holds the surplus elements.
+    Shorter(usize, Rhs),
+    /// The right-hand side ran out first, after this many matching elements.
+    /// The left-hand side tail holds the surplus elements.
+    Longer(usize, Lhs),
+    /// The two iterators agreed element-for-element and ended together.
+    Equal,
+}
+
 /// An iterator of the differences between of two iterators.
+///
+/// `F` is the comparator deciding [`Keep`](Diff::Keep) versus
+/// [`Change`](Diff::Change); [`iter_diff`](IterDiff::iter_diff) uses one built
+/// from [`PartialEq`], while [`iter_diff_by`](IterDiff::iter_diff_by) carries
+/// a user-supplied closure.
 #[derive(Debug, Hash, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
-pub struct DiffIter<Lhs, Rhs> {
+pub struct DiffIter<Lhs, Rhs, F> {
     lhs: Lhs,
     rhs: Rhs,
+    cmp: F,
 }
 
-impl<T, U, Lhs, Rhs> Iterator for DiffIter<Lhs, Rhs>
+/// The comparator [`iter_diff`](IterDiff::iter_diff) installs in its
+/// [`DiffIter`]: a function pointer wrapping [`PartialEq`].
+pub type PartialEqCmp<T, U> = fn(&T, &U) -> bool;
+
+/// Compare two elements with [`PartialEq`]; the default comparator of
+/// [`iter_diff`](IterDiff::iter_diff).
+fn eq_partial<T, U>(lhs: &T, rhs: &U) -> bool
 where
     T: PartialEq<U>,
+{
+    lhs == rhs
+}
+
+impl<T, U, Lhs, Rhs, F> Iterator for DiffIter<Lhs, Rhs, F>
+where
     Lhs: Iterator<Item = T>,
     Rhs: Iterator<Item = U>,
+    F: FnMut(&T, &U) -> bool,
 {
     type Item = Diff<U>;
 
@@ -66,7 +639,7 @@ where
             (None, None) => None,
             (None, Some(r)) => Some(Diff::Add(r)),
             (Some(_), None) => Some(Diff::Remove),
-            (Some(l), Some(r)) => match l == r {
+            (Some(l), Some(r)) => match (self.cmp)(&l, &r) {
                 true => Some(Diff::Keep),
                 false => Some(Diff::Change(r)),
             },
@@ -95,7 +668,102 @@ pub trait IterDiff<T>: IntoIterator<Item = T> + sealed::Sealed<T> {
     fn iter_diff<U, Rhs>(
         self,
         rhs: Rhs,
-    ) -> DiffIter<Self::IntoIter, Rhs::IntoIter>
+    ) -> DiffIter<Self::IntoIter, Rhs::IntoIter, PartialEqCmp<T, U>>
+    where
+        T: PartialEq<U>,
+        Rhs: IntoIterator<Item = U>;
+
+    /// Return an iterator through the differences of each element, using a
+    /// custom similarity predicate instead of [`PartialEq`].
+    ///
+    /// The comparator `f` decides only whether an aligned pair counts as
+    /// [`Keep`](Diff::Keep) or [`Change`](Diff::Change); the
+    /// [`Add`](Diff::Add)/[`Remove`](Diff::Remove) behaviour is unchanged.
+    /// This allows diffing types with no meaningful [`PartialEq`], or treating
+    /// distinct values as "close enough" — a key field, case-insensitive
+    /// strings, or floats within a tolerance.
+    ///
+    /// ```
+    /// use iter_diff::prelude::*;
+    ///
+    /// let a = [1, 2, 3];
+    /// let b = [1, 5, 3];
+    ///
+    /// // Treat two numbers as the same when they share parity.
+    /// let diffs: Vec<_> =
+    ///     a.iter_diff_by(b, |l, r| l % 2 == r % 2).collect();
+    /// assert_eq!(diffs.len(), 3);
+    ///
+    /// assert_eq!(diffs[0], Diff::Keep);
+    /// assert_eq!(diffs[1], Diff::Change(5));
+    /// assert_eq!(diffs[2], Diff::Keep);
+    /// ```
+    fn iter_diff_by<U, Rhs, F>(
+        self,
+        rhs: Rhs,
+        f: F,
+    ) -> DiffIter<Self::IntoIter, Rhs::IntoIter, F>
+    where
+        Rhs: IntoIterator<Item = U>,
+        F: FnMut(&T, &U) -> bool;
+
+    /// Return the minimal edit script aligning the two iterators by their
+    /// longest common subsequence.
+    ///
+    /// Unlike [`iter_diff`](IterDiff::iter_diff), which compares elements
+    /// positionally, this matches equal elements wherever they occur, so
+    /// inserting or deleting a single element no longer reports everything
+    /// after it as changed. The two sides are collected into [`Vec`]s and a
+    /// dynamic-programming table is walked to emit [`Keep`](Diff::Keep),
+    /// [`Remove`](Diff::Remove) and [`Add`](Diff::Add); [`Change`](Diff::Change)
+    /// is never produced by this mode.
+    ///
+    /// ```
+    /// use iter_diff::prelude::*;
+    ///
+    /// let a = [1, 2, 3];
+    /// let b = [0, 1, 2, 3];
+    ///
+    /// let diffs = a.iter_diff_lcs(b);
+    /// assert_eq!(diffs.len(), 4);
+    ///
+    /// assert_eq!(diffs[0], Diff::Add(0));
+    /// assert_eq!(diffs[1], Diff::Keep);
+    /// assert_eq!(diffs[2], Diff::Keep);
+    /// assert_eq!(diffs[3], Diff::Keep);
+    /// ```
+    fn iter_diff_lcs<U, Rhs>(self, rhs: Rhs) -> Vec<Diff<U>>
+    where
+        T: PartialEq<U>,
+        Rhs: IntoIterator<Item = U>;
+
+    /// Find where the two iterators first diverge, short-circuiting on the
+    /// first difference.
+    ///
+    /// When equality is the expected case this is far cheaper than collecting
+    /// a whole `Vec<Diff>`: it stops at the first mismatch and hands back the
+    /// un-consumed tails through a [`Divergence`], repositioned at the point
+    /// of divergence.
+    ///
+    /// ```
+    /// use iter_diff::prelude::*;
+    ///
+    /// let a = [1, 2, 3];
+    /// let b = [1, 9, 3];
+    ///
+    /// match a.first_mismatch(b) {
+    ///     Divergence::FirstMismatch(index, mut lhs, mut rhs) => {
+    ///         assert_eq!(index, 1);
+    ///         assert_eq!(lhs.next(), Some(2));
+    ///         assert_eq!(rhs.next(), Some(9));
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    fn first_mismatch<U, Rhs>(
+        self,
+        rhs: Rhs,
+    ) -> Divergence<Rejoined<T, Self::IntoIter>, Rejoined<U, Rhs::IntoIter>>
     where
         T: PartialEq<U>,
         Rhs: IntoIterator<Item = U>;
@@ -108,14 +776,92 @@ where
     fn iter_diff<U, Rhs>(
         self,
         rhs: Rhs,
-    ) -> DiffIter<Lhs::IntoIter, Rhs::IntoIter>
+    ) -> DiffIter<Lhs::IntoIter, Rhs::IntoIter, PartialEqCmp<T, U>>
     where
         T: PartialEq<U>,
         Rhs: IntoIterator<Item = U>,
     {
         let lhs = self.into_iter();
         let rhs = rhs.into_iter();
-        DiffIter { lhs, rhs }
+        DiffIter { lhs, rhs, cmp: eq_partial }
+    }
+
+    fn iter_diff_by<U, Rhs, F>(
+        self,
+        rhs: Rhs,
+        f: F,
+    ) -> DiffIter<Lhs::IntoIter, Rhs::IntoIter, F>
+    where
+        Rhs: IntoIterator<Item = U>,
+        F: FnMut(&T, &U) -> bool,
+    {
+        let lhs = self.into_iter();
+        let rhs = rhs.into_iter();
+        DiffIter { lhs, rhs, cmp: f }
+    }
+
+    fn iter_diff_lcs<U, Rhs>(self, rhs: Rhs) -> Vec<Diff<U>>
+    where
+        T: PartialEq<U>,
+        Rhs: IntoIterator<Item = U>,
+    {
+        let lhs: Vec<T> = self.into_iter().collect();
+        let rhs: Vec<U> = rhs.into_iter().collect();
+
+        let steps = lcs_script(lhs.len(), rhs.len(), |i, j| lhs[i] == rhs[j]);
+
+        let mut rhs = rhs.into_iter();
+        let mut diffs = Vec::with_capacity(steps.len());
+        for step in steps {
+            match step {
+                LcsStep::Keep => {
+                    rhs.next();
+                    diffs.push(Diff::Keep);
+                }
+                LcsStep::Remove => diffs.push(Diff::Remove),
+                LcsStep::Add => {
+                    let added =
+                        rhs.next().expect("add steps stay within the rhs");
+                    diffs.push(Diff::Add(added));
+                }
+            }
+        }
+        diffs
+    }
+
+    fn first_mismatch<U, Rhs>(
+        self,
+        rhs: Rhs,
+    ) -> Divergence<Rejoined<T, Lhs::IntoIter>, Rejoined<U, Rhs::IntoIter>>
+    where
+        T: PartialEq<U>,
+        Rhs: IntoIterator<Item = U>,
+    {
+        let mut lhs = self.into_iter();
+        let mut rhs = rhs.into_iter();
+        let mut index = 0;
+        loop {
+            match (lhs.next(), rhs.next()) {
+                (None, None) => return Divergence::Equal,
+                (None, Some(r)) => {
+                    return Divergence::Shorter(index, once(r).chain(rhs))
+                }
+                (Some(l), None) => {
+                    return Divergence::Longer(index, once(l).chain(lhs))
+                }
+                (Some(l), Some(r)) => {
+                    if l == r {
+                        index += 1;
+                    } else {
+                        return Divergence::FirstMismatch(
+                            index,
+                            once(l).chain(lhs),
+                            once(r).chain(rhs),
+                        );
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -171,6 +917,220 @@ mod tests {
         assert_eq!(diffs[3], Diff::Keep);
     }
 
+    #[test]
+    fn slice_diff_blocks() {
+        let a = [0, 1, 2, 3, 4];
+        let b = [0, 1, 9, 3, 4, 5];
+
+        let ops = slice_diff(&a, &b);
+        assert_eq!(ops.len(), 4);
+
+        assert_eq!(ops[0], DiffOp::Equal(&[0, 1]));
+        assert_eq!(ops[1], DiffOp::Replace(&[2], &[9]));
+        assert_eq!(ops[2], DiffOp::Equal(&[3, 4]));
+        assert_eq!(ops[3], DiffOp::Insert(&[5]));
+    }
+
+    #[test]
+    fn slice_diff_remove_run() {
+        let a = [0, 1, 2, 3];
+        let b = [0, 3];
+
+        let ops = slice_diff(&a, &b);
+        assert_eq!(ops.len(), 3);
+
+        assert_eq!(ops[0], DiffOp::Equal(&[0]));
+        assert_eq!(ops[1], DiffOp::Remove(&[1, 2]));
+        assert_eq!(ops[2], DiffOp::Equal(&[3]));
+    }
+
+    #[test]
+    fn diff_by_key() {
+        let a = [(0, 'a'), (1, 'b'), (2, 'c')];
+        let b = [(0, 'x'), (9, 'y'), (2, 'z')];
+
+        // Compare only by the first field of each pair.
+        let diffs: Vec<_> =
+            a.iter_diff_by(b, |l, r| l.0 == r.0).collect();
+        assert_eq!(diffs.len(), 3);
+
+        assert_eq!(diffs[0], Diff::Keep);
+        assert_eq!(diffs[1], Diff::Change((9, 'y')));
+        assert_eq!(diffs[2], Diff::Keep);
+    }
+
+    #[test]
+    fn first_mismatch_shorter() {
+        let a = [0, 1];
+        let b = [0, 1, 2, 3];
+
+        match a.first_mismatch(b) {
+            Divergence::Shorter(index, mut rhs) => {
+                assert_eq!(index, 2);
+                assert_eq!(rhs.next(), Some(2));
+                assert_eq!(rhs.next(), Some(3));
+                assert_eq!(rhs.next(), None);
+            }
+            _ => panic!("expected Shorter"),
+        }
+    }
+
+    #[test]
+    fn first_mismatch_longer() {
+        let a = [0, 1, 2];
+        let b = [0, 1];
+
+        match a.first_mismatch(b) {
+            Divergence::Longer(index, mut lhs) => {
+                assert_eq!(index, 2);
+                assert_eq!(lhs.next(), Some(2));
+                assert_eq!(lhs.next(), None);
+            }
+            _ => panic!("expected Longer"),
+        }
+    }
+
+    #[test]
+    fn first_mismatch_equal() {
+        let a = [0, 1, 2];
+        let b = [0, 1, 2];
+
+        assert!(matches!(a.first_mismatch(b), Divergence::Equal));
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl DiffHook for Recorder {
+        type Error = ();
+
+        fn equal(&mut self, old: usize, new: usize, len: usize) -> Result<(), ()> {
+            self.events.push(format!("equal {old} {new} {len}"));
+            Ok(())
+        }
+
+        fn delete(&mut self, old: usize, len: usize) -> Result<(), ()> {
+            self.events.push(format!("delete {old} {len}"));
+            Ok(())
+        }
+
+        fn insert(&mut self, new: usize, len: usize) -> Result<(), ()> {
+            self.events.push(format!("insert {new} {len}"));
+            Ok(())
+        }
+
+        fn replace(
+            &mut self,
+            old: usize,
+            old_len: usize,
+            new: usize,
+            new_len: usize,
+        ) -> Result<(), ()> {
+            self.events
+                .push(format!("replace {old} {old_len} {new} {new_len}"));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn hook_lcs_events() {
+        let a = [0, 1, 2, 3];
+        let b = [0, 2, 3, 4];
+
+        let mut rec = Recorder::default();
+        drive_diff(DiffMode::Lcs, &a, &b, &mut rec).unwrap();
+
+        assert_eq!(
+            rec.events,
+            vec![
+                "equal 0 0 1".to_string(),
+                "delete 1 1".to_string(),
+                "equal 2 1 2".to_string(),
+                "insert 3 1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn hook_positional_events() {
+        let a = [0, 1, 2, 3];
+        let b = [0, 9, 2];
+
+        let mut rec = Recorder::default();
+        drive_diff(DiffMode::Positional, &a, &b, &mut rec).unwrap();
+
+        assert_eq!(
+            rec.events,
+            vec![
+                "equal 0 0 1".to_string(),
+                "replace 1 1 1 1".to_string(),
+                "equal 2 2 1".to_string(),
+                "delete 3 1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn hook_aborts_on_error() {
+        struct Stop;
+        impl DiffHook for Stop {
+            type Error = &'static str;
+
+            fn delete(&mut self, _: usize, _: usize) -> Result<(), Self::Error> {
+                Err("stop")
+            }
+        }
+
+        let a = [0, 1, 2];
+        let b = [0, 2];
+
+        let err = drive_diff(DiffMode::Lcs, &a, &b, &mut Stop).unwrap_err();
+        assert_eq!(err, "stop");
+    }
+
+    #[test]
+    fn apply_round_trip_positional() {
+        let a = [0, 1, 2, 3];
+        let b = [0, 9, 2];
+
+        let diffs: Vec<_> = a.iter_diff(b).collect();
+        let rebuilt: Vec<_> = apply(a, diffs).collect();
+        assert_eq!(rebuilt, b);
+    }
+
+    #[test]
+    fn apply_round_trip_lcs() {
+        let a = [0, 1, 2, 3];
+        let b = [9, 0, 1, 3];
+
+        let diffs = a.iter_diff_lcs(b);
+        let rebuilt: Vec<_> = apply(a, diffs).collect();
+        assert_eq!(rebuilt, b);
+    }
+
+    #[test]
+    fn apply_checked_ok() {
+        let a = [0, 1, 2, 3];
+        let b = [0, 1, 2, 3, 4];
+
+        let diffs = a.iter_diff_lcs(b);
+        assert_eq!(apply_checked(a, diffs), Ok(b.to_vec()));
+    }
+
+    #[test]
+    fn apply_checked_base_exhausted() {
+        let err = apply_checked([0, 1], [Diff::Keep, Diff::Keep, Diff::Remove]);
+        assert_eq!(err, Err(ApplyError::BaseExhausted));
+    }
+
+    #[test]
+    fn apply_checked_base_not_consumed() {
+        let err = apply_checked([0, 1, 2], [Diff::Keep]);
+        assert_eq!(err, Err(ApplyError::BaseNotConsumed));
+    }
+
     struct TestInt(i32);
     impl PartialEq<i32> for TestInt {
         fn eq(&self, other: &i32) -> bool {
@@ -178,6 +1138,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lcs_front_insert() {
+        let a = [0, 1, 2, 3];
+        let b = [9, 0, 1, 2, 3];
+
+        let diffs = a.iter_diff_lcs(b);
+        assert_eq!(diffs.len(), 5);
+
+        assert_eq!(diffs[0], Diff::Add(9));
+        assert_eq!(diffs[1], Diff::Keep);
+        assert_eq!(diffs[2], Diff::Keep);
+        assert_eq!(diffs[3], Diff::Keep);
+        assert_eq!(diffs[4], Diff::Keep);
+    }
+
+    #[test]
+    fn lcs_remove_middle() {
+        let a = [0, 1, 2, 3];
+        let b = [0, 1, 3];
+
+        let diffs = a.iter_diff_lcs(b);
+        assert_eq!(diffs.len(), 4);
+
+        assert_eq!(diffs[0], Diff::Keep);
+        assert_eq!(diffs[1], Diff::Keep);
+        assert_eq!(diffs[2], Diff::Remove);
+        assert_eq!(diffs[3], Diff::Keep);
+    }
+
     #[test]
     fn add_mixed() {
         let a = [TestInt(0), TestInt(2)];
@@ -0,0 +1,11 @@
+//! Re-exports of the crate's public items for glob import.
+//!
+//! ```
+//! use iter_diff::prelude::*;
+//! ```
+
+pub use crate::{
+    apply, apply_checked, drive_diff, slice_diff, Apply, ApplyError, Diff,
+    DiffHook, DiffIter, DiffMode, DiffOp, Divergence, IterDiff, PartialEqCmp,
+    Rejoined,
+};